@@ -0,0 +1,12 @@
+use craite::{BlockingCraiteClient, CraiteConfig};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+    let client = BlockingCraiteClient::with_config(CraiteConfig::from_env()?)?;
+
+    let result = client.generate("Create a DAO contract with voting")?;
+
+    println!("Generated contract:\n{}", result.code);
+
+    Ok(())
+}
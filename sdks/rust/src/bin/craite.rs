@@ -0,0 +1,101 @@
+use clap::{Parser, Subcommand};
+use craite::{CraiteClient, CraiteConfig};
+
+/// CRAITE Web3 code generation from the command line.
+///
+/// Credentials are read from the environment (`CRAITE_API_KEY`, plus the
+/// optional `CRAITE_ENDPOINT` and `CRAITE_TIMEOUT`), including a `.env` file.
+#[derive(Parser)]
+#[command(name = "craite", version)]
+struct Cli {
+    /// Print machine-readable JSON instead of plain text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate code from a natural-language prompt
+    Generate {
+        /// What to build, e.g. "Create an ERC-20 token"
+        #[arg(
+            long,
+            required_unless_present = "positional_prompt",
+            conflicts_with = "positional_prompt"
+        )]
+        prompt: Option<String>,
+
+        /// The prompt may also be given without `--prompt`
+        #[arg(value_name = "PROMPT")]
+        positional_prompt: Option<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let client = CraiteClient::with_config(CraiteConfig::from_env()?)?;
+
+    match cli.command {
+        Command::Generate {
+            prompt,
+            positional_prompt,
+        } => {
+            // clap guarantees exactly one of the two is present
+            let prompt = prompt.or(positional_prompt).unwrap_or_default();
+            let result = client.generate(&prompt).await?;
+            if cli.json {
+                let output = serde_json::json!({
+                    "code": result.code,
+                    "explanation": result.explanation,
+                    "language": result.language,
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else {
+                println!("{}", result.code);
+                if let Some(explanation) = result.explanation {
+                    eprintln!("\n{}", explanation);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_prompt(args: &[&str]) -> (String, bool) {
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Command::Generate {
+                prompt,
+                positional_prompt,
+            } => (prompt.or(positional_prompt).unwrap(), cli.json),
+        }
+    }
+
+    #[test]
+    fn test_generate_prompt_flag_and_json() {
+        assert_eq!(
+            parse_prompt(&["craite", "generate", "--prompt", "Create a DAO", "--json"]),
+            ("Create a DAO".to_string(), true)
+        );
+        assert_eq!(
+            parse_prompt(&["craite", "generate", "Create a DAO"]),
+            ("Create a DAO".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn test_generate_requires_exactly_one_prompt() {
+        assert!(Cli::try_parse_from(["craite", "generate"]).is_err());
+        assert!(Cli::try_parse_from(["craite", "generate", "--prompt", "a", "b"]).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! A synchronous facade over [`CraiteClient`] for callers without a Tokio
+//! runtime.
+//!
+//! Each `BlockingCraiteClient` owns a single-threaded runtime and blocks the
+//! calling thread on it. That keeps scripts free of `#[tokio::main]`, at the
+//! cost of one runtime per client and no concurrency between calls. Do not
+//! use it from inside an async context: blocking there panics, so async code
+//! should use [`CraiteClient`] directly.
+
+use crate::client::{CraiteClient, CraiteConfig, GenerateResult};
+use crate::prompt::PromptTemplate;
+use anyhow::Result;
+use tokio::runtime::{Builder, Runtime};
+
+#[derive(Debug)]
+pub struct BlockingCraiteClient {
+    inner: CraiteClient,
+    runtime: Runtime,
+}
+
+impl BlockingCraiteClient {
+    pub fn new(api_key: &str) -> Result<Self> {
+        Self::with_config(CraiteConfig {
+            api_key: api_key.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn with_config(config: CraiteConfig) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner: CraiteClient::with_config(config)?,
+            runtime,
+        })
+    }
+
+    pub fn api_key(&self) -> &str {
+        self.inner.api_key()
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    pub fn user_agent(&self) -> Option<&str> {
+        self.inner.user_agent()
+    }
+
+    pub fn generate(&self, prompt: &str) -> Result<GenerateResult> {
+        self.runtime.block_on(self.inner.generate(prompt))
+    }
+
+    /// Render `template` with `vars` and generate from the result
+    pub fn generate_template(
+        &self,
+        template: &PromptTemplate,
+        vars: &[(&str, &str)],
+    ) -> Result<GenerateResult> {
+        self.runtime
+            .block_on(self.inner.generate_template(template, vars))
+    }
+
+    /// The async client this facade drives
+    pub fn async_client(&self) -> &CraiteClient {
+        &self.inner
+    }
+}
@@ -1,24 +1,168 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use crate::prompt::PromptTemplate;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
-#[derive(Debug, Clone)]
+const DEFAULT_BASE_URL: &str = "https://api.craite.ai/v1";
+
+/// Cheap to clone: clones share the same HTTP client and connection pool
+#[derive(Clone)]
 pub struct CraiteClient {
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
     api_key: String,
     base_url: String,
+    /// `None` when the caller injected their own HTTP client
+    user_agent: Option<String>,
     client: reqwest::Client,
+    limiter: Option<Semaphore>,
+    mock: Option<crate::mock::MockProvider>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone, Default)]
 pub struct CraiteConfig {
     pub api_key: String,
     pub base_url: Option<String>,
+    /// Total time allowed for a single request
+    pub timeout: Option<Duration>,
+    /// Appended to the SDK's `User-Agent` to identify the calling application
+    pub user_agent_suffix: Option<String>,
+    /// Maximum idle connections kept per host
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection is kept before being closed
+    pub pool_idle_timeout: Option<Duration>,
+    pub tcp_keepalive: Option<Duration>,
+    /// Speak HTTP/2 without ALPN negotiation
+    pub http2_prior_knowledge: bool,
+    /// Reuse an existing HTTP client instead of building one. The timeout, user
+    /// agent and pool settings above are ignored when this is set.
+    pub http_client: Option<reqwest::Client>,
+    /// Most `generate` calls allowed in flight at once; further calls wait
+    /// for a slot. Shared by all clones of the client.
+    pub max_concurrency: Option<usize>,
+    /// Serve every `generate` call from a mock instead of the API
+    pub mock: Option<crate::mock::MockProvider>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct GenerateOptions {
     pub prompt: String,
     pub language: Option<String>,
     pub mode: Option<GenerationMode>,
+    // Unset controls are omitted when serialized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+}
+
+impl GenerateOptions {
+    pub fn builder() -> GenerateOptionsBuilder {
+        GenerateOptionsBuilder::default()
+    }
+}
+
+/// Builds [`GenerateOptions`], filling in the SDK defaults for anything left
+/// unset: Solidity, production mode, temperature 0.7 and 2000 max tokens
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptionsBuilder {
+    options: GenerateOptions,
+}
+
+impl GenerateOptionsBuilder {
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.options.prompt = prompt.into();
+        self
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.options.language = Some(language.into());
+        self
+    }
+
+    pub fn mode(mut self, mode: GenerationMode) -> Self {
+        self.options.mode = Some(mode);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.options.temperature = Some(temperature);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.options.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Add a stop sequence; may be called repeatedly
+    pub fn stop(mut self, sequence: impl Into<String>) -> Self {
+        self.options.stop.push(sequence.into());
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.options.seed = Some(seed);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.options.top_p = Some(top_p);
+        self
+    }
+
+    pub fn presence_penalty(mut self, penalty: f32) -> Self {
+        self.options.presence_penalty = Some(penalty);
+        self
+    }
+
+    pub fn frequency_penalty(mut self, penalty: f32) -> Self {
+        self.options.frequency_penalty = Some(penalty);
+        self
+    }
+
+    /// Values are taken as given, so `temperature(5.0)` or `max_tokens(0)`
+    /// pass through; use [`try_build`](Self::try_build) to reject them
+    pub fn build(self) -> GenerateOptions {
+        let mut options = self.options;
+        options
+            .language
+            .get_or_insert_with(|| "solidity".to_string());
+        options.mode.get_or_insert(GenerationMode::Production);
+        options.temperature.get_or_insert(0.7);
+        options.max_tokens.get_or_insert(2000);
+        options
+    }
+
+    /// Like [`build`](Self::build), but rejects a zero `max_tokens` and a
+    /// temperature outside `0.0..=2.0`
+    pub fn try_build(self) -> Result<GenerateOptions> {
+        let options = self.build();
+        if options.max_tokens == Some(0) {
+            anyhow::bail!("max_tokens must be at least 1");
+        }
+        if let Some(temperature) = options.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                anyhow::bail!("temperature must be between 0 and 2, got {}", temperature);
+            }
+        }
+        Ok(options)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,30 +171,195 @@ pub enum GenerationMode {
     Educational,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Build with `..Default::default()` so added fields don't break callers
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct GenerateResult {
     pub code: String,
     pub explanation: Option<String>,
+    /// Language of `code`, when the API reports it
+    pub language: Option<String>,
+}
+
+// Debug is written by hand for the client and its config so the API key
+// never ends up in logs or traces
+const REDACTED: &str = "***";
+
+impl fmt::Debug for CraiteConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CraiteConfig")
+            .field("api_key", &REDACTED)
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("user_agent_suffix", &self.user_agent_suffix)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge)
+            // reqwest's Debug prints default headers, which may carry credentials
+            .field(
+                "http_client",
+                &self.http_client.as_ref().map(|_| "<injected>"),
+            )
+            .field("max_concurrency", &self.max_concurrency)
+            .field("mock", &self.mock)
+            .finish()
+    }
+}
+
+impl fmt::Debug for CraiteClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CraiteClient")
+            .field("api_key", &REDACTED)
+            .field("base_url", &self.inner.base_url)
+            .field("user_agent", &self.inner.user_agent)
+            .field("mock", &self.inner.mock)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CraiteConfig {
+    /// Read configuration from `CRAITE_API_KEY` (required), `CRAITE_ENDPOINT`
+    /// and `CRAITE_TIMEOUT` (in seconds)
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("CRAITE_API_KEY").unwrap_or_default();
+        let api_key = api_key.trim();
+        if api_key.is_empty() {
+            anyhow::bail!("CRAITE_API_KEY is not set or empty");
+        }
+        // An empty value, as left by many `.env` templates, means "use the default"
+        let base_url = std::env::var("CRAITE_ENDPOINT")
+            .ok()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        let timeout = match std::env::var("CRAITE_TIMEOUT") {
+            Ok(secs) => {
+                let secs = secs.trim().parse::<u64>().ok().filter(|&secs| secs > 0);
+                let secs = secs.ok_or_else(|| {
+                    anyhow::anyhow!("CRAITE_TIMEOUT must be a positive whole number of seconds")
+                })?;
+                Some(Duration::from_secs(secs))
+            }
+            Err(_) => None,
+        };
+
+        Ok(Self {
+            api_key: api_key.to_string(),
+            base_url,
+            timeout,
+            ..Default::default()
+        })
+    }
 }
 
 impl CraiteClient {
     pub fn new(api_key: &str) -> Self {
-        Self {
+        Self::with_config(CraiteConfig {
             api_key: api_key.to_string(),
-            base_url: "https://api.craite.ai/v1".to_string(),
-            client: reqwest::Client::new(),
-        }
+            ..Default::default()
+        })
+        .expect("failed to build HTTP client")
+    }
+
+    pub fn with_config(config: CraiteConfig) -> Result<Self> {
+        let (client, user_agent) = match config.http_client.clone() {
+            Some(client) => (client, None),
+            None => {
+                let user_agent = build_user_agent(config.user_agent_suffix.as_deref());
+                (build_http_client(&config, &user_agent)?, Some(user_agent))
+            }
+        };
+        let limiter = match config.max_concurrency {
+            Some(0) => anyhow::bail!("max_concurrency must be at least 1"),
+            Some(limit) => Some(Semaphore::new(limit)),
+            None => None,
+        };
+
+        Ok(Self {
+            inner: Arc::new(ClientInner {
+                api_key: config.api_key,
+                base_url: config
+                    .base_url
+                    .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+                user_agent,
+                client,
+                limiter,
+                mock: config.mock,
+            }),
+        })
     }
 
     pub fn api_key(&self) -> &str {
-        &self.api_key
+        &self.inner.api_key
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.inner.base_url
+    }
+
+    /// The `User-Agent` the SDK sends, or `None` for an injected HTTP client,
+    /// whose headers are left as the caller configured them
+    pub fn user_agent(&self) -> Option<&str> {
+        self.inner.user_agent.as_deref()
+    }
+
+    /// The underlying HTTP client: either the one injected through
+    /// [`CraiteConfig::http_client`], or one built with the SDK's `User-Agent`
+    /// and the configured timeout and pool settings
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.inner.client
     }
 
     pub async fn generate(&self, prompt: &str) -> Result<GenerateResult> {
+        let _permit = match &self.inner.limiter {
+            Some(limiter) => Some(limiter.acquire().await?),
+            None => None,
+        };
+
+        if let Some(mock) = &self.inner.mock {
+            return Ok(mock.respond(prompt).await);
+        }
+
         // Placeholder implementation
         Ok(GenerateResult {
             code: format!("// Generated code for: {}", prompt),
             explanation: Some("This is a placeholder implementation".to_string()),
+            ..Default::default()
         })
     }
+
+    /// Render `template` with `vars` and generate from the result
+    pub async fn generate_template(
+        &self,
+        template: &PromptTemplate,
+        vars: &[(&str, &str)],
+    ) -> Result<GenerateResult> {
+        self.generate(&template.render(vars)?).await
+    }
+}
+
+fn build_http_client(config: &CraiteConfig, user_agent: &str) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .tcp_keepalive(config.tcp_keepalive);
+    if let Some(timeout) = config.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(max_idle) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout) = config.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(idle_timeout);
+    }
+    if config.http2_prior_knowledge {
+        builder = builder.http2_prior_knowledge();
+    }
+    Ok(builder.build()?)
+}
+
+fn build_user_agent(suffix: Option<&str>) -> String {
+    let base = format!("craite-rust-sdk/{}", env!("CARGO_PKG_VERSION"));
+    match suffix {
+        Some(suffix) if !suffix.trim().is_empty() => format!("{} {}", base, suffix.trim()),
+        _ => base,
+    }
 }
@@ -1,10 +1,23 @@
 //! CRAITE Rust SDK for Web3 AI Development
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 pub mod mcp;
+pub mod mock;
+mod output;
+pub mod prompt;
+pub mod tokens;
 
-pub use client::{CraiteClient, CraiteConfig};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingCraiteClient;
+pub use client::{
+    CraiteClient, CraiteConfig, GenerateOptions, GenerateOptionsBuilder, GenerationMode,
+};
 pub use mcp::MCPTool;
+pub use mock::MockProvider;
+pub use prompt::PromptTemplate;
+pub use tokens::estimate_tokens;
 
 /// Create a new CRAITE client
 pub fn create_client(api_key: &str) -> CraiteClient {
@@ -1,3 +1,5 @@
+pub mod params;
+
 #[derive(Debug, Clone)]
 pub struct MCPTool {
     pub name: String,
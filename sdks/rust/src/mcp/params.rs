@@ -0,0 +1,136 @@
+//! Typed access to MCP tool parameters
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A parameter map that does not fit the shape a tool expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    Missing { field: String },
+    InvalidType { field: String, message: String },
+    Unknown { field: String },
+    Other(String),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Missing { field } => write!(f, "missing parameter `{}`", field),
+            ParamError::InvalidType { field, message } => {
+                write!(f, "invalid parameter `{}`: {}", field, message)
+            }
+            ParamError::Unknown { field } => write!(f, "unknown parameter `{}`", field),
+            ParamError::Other(message) => write!(f, "invalid parameters: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl de::Error for ParamError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ParamError::Other(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        ParamError::Missing {
+            field: field.to_string(),
+        }
+    }
+
+    fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+        ParamError::Unknown {
+            field: field.to_string(),
+        }
+    }
+}
+
+/// Deserialize a tool's parameter map into a typed struct.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct DeployParams {
+///     contract_name: String,
+///     #[serde(default)]
+///     verify: bool,
+/// }
+///
+/// let mut params = HashMap::new();
+/// params.insert("contract_name".to_string(), serde_json::json!("Token"));
+///
+/// let parsed: DeployParams = craite::mcp::params::parse(&params).unwrap();
+/// assert_eq!(parsed.contract_name, "Token");
+/// assert!(!parsed.verify);
+/// ```
+pub fn parse<T: DeserializeOwned>(params: &HashMap<String, Value>) -> Result<T, ParamError> {
+    T::deserialize(ParamsDeserializer { params })
+}
+
+struct ParamsDeserializer<'a> {
+    params: &'a HashMap<String, Value>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ParamsDeserializer<'a> {
+    type Error = ParamError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        // Sorted so that, with several bad parameters, the same one is reported
+        // every time
+        let mut entries: Vec<_> = self.params.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        visitor.visit_map(ParamsMapAccess {
+            entries: entries.into_iter(),
+            current: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Walks the map while remembering which key is being read, so value errors
+/// can name the offending field
+struct ParamsMapAccess<'a> {
+    entries: std::vec::IntoIter<(&'a String, &'a Value)>,
+    current: Option<(&'a String, &'a Value)>,
+}
+
+impl<'de, 'a> MapAccess<'de> for ParamsMapAccess<'a> {
+    type Error = ParamError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ParamError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.current = Some((key, value));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, ParamError> {
+        let (key, value) = self
+            .current
+            .take()
+            .ok_or_else(|| ParamError::Other("value requested before key".to_string()))?;
+        seed.deserialize(value.clone())
+            .map_err(|err| ParamError::InvalidType {
+                field: key.clone(),
+                message: err.to_string(),
+            })
+    }
+}
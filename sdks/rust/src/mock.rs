@@ -0,0 +1,64 @@
+//! Canned responses for testing code built on the SDK without network access
+
+use crate::client::GenerateResult;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type Response = Pin<Box<dyn Future<Output = GenerateResult> + Send>>;
+type Responder = dyn Fn(&str) -> Response + Send + Sync;
+
+/// Answers `generate` calls from a closure instead of the API.
+///
+/// Attach it through `CraiteConfig::mock`; the client then never performs a
+/// request and returns whatever the closure builds for the prompt. The type
+/// is always available so `CraiteConfig` has the same fields in every build,
+/// but it can only be constructed with the `testing` feature.
+#[derive(Clone)]
+pub struct MockProvider {
+    responder: Arc<Responder>,
+}
+
+impl MockProvider {
+    #[cfg(feature = "testing")]
+    pub fn new<F>(responder: F) -> Self
+    where
+        F: Fn(&str) -> GenerateResult + Send + Sync + 'static,
+    {
+        Self::new_async(move |prompt| std::future::ready(responder(prompt)))
+    }
+
+    /// Respond from an async closure, e.g. to simulate latency with
+    /// `tokio::time::sleep` without blocking a runtime worker
+    #[cfg(feature = "testing")]
+    pub fn new_async<F, Fut>(responder: F) -> Self
+    where
+        F: Fn(&str) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = GenerateResult> + Send + 'static,
+    {
+        Self {
+            responder: Arc::new(move |prompt| Box::pin(responder(prompt))),
+        }
+    }
+
+    /// Always respond with the same code
+    #[cfg(feature = "testing")]
+    pub fn with_code(code: &str) -> Self {
+        let code = code.to_string();
+        Self::new(move |_| GenerateResult {
+            code: code.clone(),
+            ..Default::default()
+        })
+    }
+
+    pub async fn respond(&self, prompt: &str) -> GenerateResult {
+        (self.responder)(prompt).await
+    }
+}
+
+impl fmt::Debug for MockProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockProvider").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,184 @@
+//! Writing generated code to disk
+
+use crate::client::GenerateResult;
+use anyhow::Result;
+use regex::Regex;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const FOUNDRY_TOML: &str = r#"[profile.default]
+src = "src"
+out = "out"
+libs = ["lib"]
+remappings = ["@openzeppelin/contracts/=lib/openzeppelin-contracts/contracts/"]
+"#;
+
+const LIB_README: &str = "Dependencies live here. Install OpenZeppelin with:
+
+    forge install OpenZeppelin/openzeppelin-contracts
+";
+
+fn contract_declaration() -> &'static Regex {
+    static CONTRACT: OnceLock<Regex> = OnceLock::new();
+    CONTRACT.get_or_init(|| {
+        Regex::new(r"(?m)^\s*(abstract\s+)?contract\s+([A-Za-z_$][A-Za-z0-9_$]*)").unwrap()
+    })
+}
+
+impl GenerateResult {
+    /// File extension for `language`, falling back to `txt` when it is
+    /// unknown or missing
+    pub fn file_extension(&self) -> &'static str {
+        match self
+            .language
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("solidity") => "sol",
+            Some("rust") => "rs",
+            Some("move") => "move",
+            Some("vyper") => "vy",
+            Some("python") => "py",
+            Some("typescript") => "ts",
+            Some("javascript") => "js",
+            Some("go") => "go",
+            _ => "txt",
+        }
+    }
+
+    /// Write `code` into `dir`, creating the directory if needed, and return
+    /// the path written.
+    ///
+    /// Solidity is saved as `<ContractName>.sol` and anything else as
+    /// `generated.<ext>`. Existing files are never overwritten: a taken name
+    /// gets a numeric suffix instead, e.g. `Token-2.sol`.
+    pub fn save_to_file(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let extension = self.file_extension();
+        let stem = match extension {
+            "sol" => self.contract_name().unwrap_or("generated"),
+            _ => "generated",
+        };
+
+        for n in 1.. {
+            let path = match n {
+                1 => dir.join(format!("{}.{}", stem, extension)),
+                _ => dir.join(format!("{}-{}.{}", stem, n, extension)),
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(self.code.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        unreachable!("ran out of file names in {}", dir.display())
+    }
+
+    /// Name of the main contract in `code`: the last concrete `contract`
+    /// declaration outside comments, since helpers and bases usually come
+    /// first
+    pub fn contract_name(&self) -> Option<&str> {
+        let code = blank_comments(&self.code);
+        let mut found = None;
+        for caps in contract_declaration().captures_iter(&code) {
+            if caps.get(1).is_none() {
+                found = caps.get(2).map(|name| name.range());
+            }
+        }
+        // Blanking keeps byte offsets, so the range indexes the original
+        found.map(|range| &self.code[range])
+    }
+
+    /// Lay out a Foundry project at `path`, with the code in
+    /// `src/<ContractName>.sol`, ready for `forge build` once OpenZeppelin is
+    /// installed into `lib/`.
+    ///
+    /// Fails if `language` names something other than Solidity, or if `path`
+    /// already holds a `foundry.toml`; an existing project is never
+    /// overwritten.
+    pub fn to_foundry_project(&self, path: impl AsRef<Path>) -> Result<()> {
+        let root = path.as_ref();
+        if let Some(language) = &self.language {
+            if !language.eq_ignore_ascii_case("solidity") {
+                anyhow::bail!("Foundry projects need Solidity code, got {}", language);
+            }
+        }
+        if root.join("foundry.toml").exists() {
+            anyhow::bail!("A Foundry project already exists at {}", root.display());
+        }
+        let name = self
+            .contract_name()
+            .ok_or_else(|| anyhow::anyhow!("No contract declaration found in generated code"))?;
+
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("lib"))?;
+        fs::write(root.join("src").join(format!("{}.sol", name)), &self.code)?;
+        fs::write(root.join("foundry.toml"), FOUNDRY_TOML)?;
+        fs::write(root.join("lib").join("README.md"), LIB_README)?;
+        Ok(())
+    }
+}
+
+/// Replace comments with spaces, keeping newlines and byte offsets intact.
+/// String literals are skipped so a `//` inside one is not a comment.
+fn blank_comments(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut chars = code.chars().peekable();
+    let blank = |out: &mut String, c: char| {
+        if c == '\n' {
+            out.push('\n');
+        } else {
+            out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                blank(&mut out, c);
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    blank(&mut out, next);
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                blank(&mut out, c);
+                blank(&mut out, chars.next().unwrap());
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    blank(&mut out, next);
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            '"' | '\'' => {
+                out.push(c);
+                let mut escaped = false;
+                for next in chars.by_ref() {
+                    out.push(next);
+                    if escaped {
+                        escaped = false;
+                    } else if next == '\\' {
+                        escaped = true;
+                    } else if next == c || next == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
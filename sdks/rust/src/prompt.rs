@@ -0,0 +1,102 @@
+//! Reusable prompts with `{variable}` placeholders
+
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+fn token() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    // Escapes come first so `{{name}}` is read as a literal, not a placeholder
+    TOKEN.get_or_init(|| Regex::new(r"\{\{|\}\}|\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap())
+}
+
+/// A run of literal text or a placeholder name
+enum Piece<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut open_escapes = 0usize;
+    let mut copied = 0;
+    for caps in token().captures_iter(template) {
+        let matched = caps.get(0).unwrap();
+        let piece = match caps.get(1) {
+            Some(name) => Piece::Variable(name.as_str()),
+            None if matched.as_str() == "{{" => {
+                open_escapes += 1;
+                Piece::Text("{")
+            }
+            None if open_escapes > 0 => {
+                open_escapes -= 1;
+                Piece::Text("}")
+            }
+            // A `}}` that closes no `{{` is ordinary code and stays as written
+            None => continue,
+        };
+        pieces.push(Piece::Text(&template[copied..matched.start()]));
+        pieces.push(piece);
+        copied = matched.end();
+    }
+    pieces.push(Piece::Text(&template[copied..]));
+    pieces
+}
+
+/// A prompt skeleton such as `"Create a {kind} named {name}"`.
+///
+/// Braces that do not wrap an identifier (for example `{ }` in a code
+/// snippet) are left untouched. `{{` is always an escaped `{`, and `}}` is an
+/// escaped `}` only when it closes an earlier `{{`, so
+/// `"function f() public {{revert}}"` renders as `function f() public {revert}`
+/// while nested blocks such as `{ if (a) { b(); }}` keep both braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    template: String,
+}
+
+impl PromptTemplate {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+        }
+    }
+
+    /// Placeholder names in order of first appearance
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for piece in pieces(&self.template) {
+            if let Piece::Variable(name) = piece {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Substitute every placeholder, failing if any has no value
+    pub fn render(&self, vars: &[(&str, &str)]) -> Result<String> {
+        let missing: Vec<&str> = self
+            .variables()
+            .into_iter()
+            .filter(|name| !vars.iter().any(|(key, _)| key == name))
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!("Unresolved prompt variables: {}", missing.join(", "));
+        }
+
+        let mut rendered = String::with_capacity(self.template.len());
+        for piece in pieces(&self.template) {
+            match piece {
+                Piece::Text(text) => rendered.push_str(text),
+                Piece::Variable(name) => {
+                    if let Some((_, value)) = vars.iter().find(|(key, _)| *key == name) {
+                        rendered.push_str(value);
+                    }
+                }
+            }
+        }
+        Ok(rendered)
+    }
+}
@@ -0,0 +1,25 @@
+//! Rough token counts for budgeting prompts without calling an API
+
+/// Estimate how many tokens `text` uses with `model`.
+///
+/// This is a characters-per-token approximation, not a real tokenizer:
+/// expect it to land within roughly 25% of the true count for English
+/// prose, and to undercount dense code or non-Latin scripts.
+pub fn estimate_tokens(text: &str, model: &str) -> usize {
+    let chars = text.chars().count();
+    if chars == 0 {
+        return 0;
+    }
+    (chars as f64 / chars_per_token(model)).ceil() as usize
+}
+
+fn chars_per_token(model: &str) -> f64 {
+    let model = model.to_ascii_lowercase();
+    if model.starts_with("claude") {
+        3.5
+    } else {
+        // OpenAI's cl100k/o200k encodings average about four characters per
+        // token on English text, which is also a fair default for others
+        4.0
+    }
+}
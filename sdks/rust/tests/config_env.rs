@@ -0,0 +1,37 @@
+// Kept in its own test binary: setting environment variables races with any
+// other thread reading them, including reqwest looking up proxy settings
+use craite::CraiteConfig;
+use std::time::Duration;
+
+#[test]
+fn test_config_from_env() {
+    std::env::set_var("CRAITE_API_KEY", " env-key\n");
+    std::env::set_var("CRAITE_ENDPOINT", "http://localhost:8080/v1");
+    std::env::set_var("CRAITE_TIMEOUT", "45");
+
+    let config = CraiteConfig::from_env().unwrap();
+    assert_eq!(config.api_key, "env-key");
+    assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080/v1"));
+    assert_eq!(config.timeout, Some(Duration::from_secs(45)));
+
+    for invalid in ["soon", "0", "-5"] {
+        std::env::set_var("CRAITE_TIMEOUT", invalid);
+        let err = CraiteConfig::from_env().unwrap_err();
+        assert!(err.to_string().contains("CRAITE_TIMEOUT"), "{}", invalid);
+    }
+
+    std::env::remove_var("CRAITE_TIMEOUT");
+    for empty in ["", "  "] {
+        std::env::set_var("CRAITE_ENDPOINT", empty);
+        assert_eq!(CraiteConfig::from_env().unwrap().base_url, None);
+    }
+
+    std::env::remove_var("CRAITE_ENDPOINT");
+    std::env::set_var("CRAITE_API_KEY", "  ");
+    let err = CraiteConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("CRAITE_API_KEY"));
+
+    std::env::remove_var("CRAITE_API_KEY");
+    let err = CraiteConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("CRAITE_API_KEY"));
+}
@@ -4,3 +4,592 @@ async fn test_client_creation() {
 let client = create_client("test-key");
 assert_eq!(client.api_key(), "test-key");
 }
+
+#[tokio::test]
+async fn test_user_agent_header() {
+    use craite::{CraiteClient, CraiteConfig};
+
+    let expected = format!("craite-rust-sdk/{}", env!("CARGO_PKG_VERSION"));
+    let mut server = mockito::Server::new_async().await;
+
+    for (suffix, user_agent) in [
+        (None, expected.clone()),
+        (
+            Some("my-dapp/0.3".to_string()),
+            format!("{} my-dapp/0.3", expected),
+        ),
+    ] {
+        let mock = server
+            .mock("GET", "/")
+            .match_header("user-agent", user_agent.as_str())
+            .create_async()
+            .await;
+
+        let client = CraiteClient::with_config(CraiteConfig {
+            api_key: "test-key".to_string(),
+            base_url: Some(server.url()),
+            user_agent_suffix: suffix,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(client.user_agent(), Some(user_agent.as_str()));
+
+        client
+            .http_client()
+            .get(client.base_url())
+            .send()
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_cloned_clients_share_http_client() {
+    use craite::{CraiteClient, CraiteConfig};
+    use std::time::Duration;
+
+    let client = CraiteClient::with_config(CraiteConfig {
+        api_key: "test-key".to_string(),
+        pool_max_idle_per_host: Some(4),
+        pool_idle_timeout: Some(Duration::from_secs(30)),
+        tcp_keepalive: Some(Duration::from_secs(60)),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let clones: Vec<CraiteClient> = (0..16).map(|_| client.clone()).collect();
+    for clone in &clones {
+        assert!(std::ptr::eq(clone.http_client(), client.http_client()));
+    }
+    assert!(!std::ptr::eq(
+        create_client("test-key").http_client(),
+        client.http_client()
+    ));
+}
+
+#[tokio::test]
+async fn test_injected_http_client_is_used() {
+    use craite::{CraiteClient, CraiteConfig};
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/")
+        .match_header("x-shared-client", "1")
+        .create_async()
+        .await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-shared-client", HeaderValue::from_static("1"));
+    let shared = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    let client = CraiteClient::with_config(CraiteConfig {
+        api_key: "test-key".to_string(),
+        base_url: Some(server.url()),
+        http_client: Some(shared),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(client.user_agent(), None);
+
+    client
+        .http_client()
+        .get(client.base_url())
+        .send()
+        .await
+        .unwrap();
+    mock.assert_async().await;
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_mock_provider_short_circuits_generate() {
+    use craite::client::GenerateResult;
+    use craite::{CraiteClient, CraiteConfig, MockProvider};
+
+    let client = CraiteClient::with_config(CraiteConfig {
+        api_key: "test-key".to_string(),
+        // Nothing listens here; any real request would fail
+        base_url: Some("http://127.0.0.1:9".to_string()),
+        mock: Some(MockProvider::new(|prompt| GenerateResult {
+            code: format!("contract Mock {{}} // {}", prompt),
+            explanation: Some("canned".to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let result = client.generate("Create a vault").await.unwrap();
+    assert_eq!(result.code, "contract Mock {} // Create a vault");
+    assert_eq!(result.explanation.as_deref(), Some("canned"));
+}
+
+#[tokio::test]
+async fn test_config_has_mock_field_in_every_build() {
+    use craite::{CraiteClient, CraiteConfig};
+
+    // Compiles with and without the testing feature
+    let client = CraiteClient::with_config(CraiteConfig {
+        api_key: "test-key".to_string(),
+        mock: None,
+        ..Default::default()
+    })
+    .unwrap();
+    let result = client.generate("Create a vault").await.unwrap();
+    assert!(result.code.contains("Create a vault"));
+}
+
+#[test]
+fn test_sampling_options_only_serialized_when_set() {
+    use craite::GenerateOptions;
+
+    let payload = serde_json::to_value(GenerateOptions::builder().build()).unwrap();
+    for field in ["top_p", "presence_penalty", "frequency_penalty"] {
+        assert!(payload.get(field).is_none(), "{} should be omitted", field);
+    }
+
+    let options = GenerateOptions::builder()
+        .top_p(0.5)
+        .presence_penalty(0.25)
+        .frequency_penalty(-0.5)
+        .build();
+    let payload = serde_json::to_value(&options).unwrap();
+    assert_eq!(payload["top_p"], 0.5);
+    assert_eq!(payload["presence_penalty"], 0.25);
+    assert_eq!(payload["frequency_penalty"], -0.5);
+}
+
+#[test]
+fn test_params_parse_round_trip() {
+    use craite::mcp::params::{self, ParamError};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct TokenParams {
+        name: String,
+        symbol: String,
+        #[serde(default)]
+        features: Vec<String>,
+        decimals: Option<u8>,
+    }
+
+    let original = TokenParams {
+        name: "MyToken".to_string(),
+        symbol: "MTK".to_string(),
+        features: vec!["Burnable".to_string()],
+        decimals: Some(18),
+    };
+    let map: HashMap<String, serde_json::Value> =
+        serde_json::from_value(serde_json::to_value(&original).unwrap()).unwrap();
+    assert_eq!(params::parse::<TokenParams>(&map).unwrap(), original);
+
+    let mut map = HashMap::new();
+    map.insert("name".to_string(), serde_json::json!("MyToken"));
+    assert_eq!(
+        params::parse::<TokenParams>(&map).unwrap_err(),
+        ParamError::Missing {
+            field: "symbol".to_string()
+        }
+    );
+
+    map.insert("symbol".to_string(), serde_json::json!(42));
+    match params::parse::<TokenParams>(&map).unwrap_err() {
+        ParamError::InvalidType { field, message } => {
+            assert_eq!(field, "symbol");
+            assert!(message.contains("expected a string"), "{}", message);
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    map.insert("symbol".to_string(), serde_json::json!("MTK"));
+    map.insert("contract_typ".to_string(), serde_json::json!("ERC20"));
+    assert_eq!(
+        params::parse::<TokenParams>(&map).unwrap_err().to_string(),
+        "unknown parameter `contract_typ`"
+    );
+
+    // Several bad parameters: the first in key order is always reported
+    let mut map = HashMap::new();
+    for key in ["zeta", "name", "alpha", "symbol", "mid"] {
+        map.insert(key.to_string(), serde_json::json!(1));
+    }
+    assert_eq!(
+        params::parse::<TokenParams>(&map).unwrap_err().to_string(),
+        "unknown parameter `alpha`"
+    );
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn test_blocking_client_generate() {
+    use craite::BlockingCraiteClient;
+
+    let client = BlockingCraiteClient::new("test-key").unwrap();
+    assert_eq!(client.api_key(), "test-key");
+
+    let result = client.generate("Create an ERC-20 token").unwrap();
+    assert!(result.code.contains("Create an ERC-20 token"));
+
+    let template = craite::PromptTemplate::new("Create a {kind} token");
+    let result = client
+        .generate_template(&template, &[("kind", "ERC-721")])
+        .unwrap();
+    assert!(result.code.contains("Create a ERC-721 token"));
+    assert!(client.generate_template(&template, &[]).is_err());
+}
+
+#[test]
+fn test_generate_options_builder() {
+    use craite::{GenerateOptions, GenerationMode};
+
+    let defaults = GenerateOptions::builder().prompt("Create a token").build();
+    assert_eq!(defaults.prompt, "Create a token");
+    assert_eq!(defaults.language.as_deref(), Some("solidity"));
+    assert!(matches!(defaults.mode, Some(GenerationMode::Production)));
+    assert_eq!(defaults.temperature, Some(0.7));
+    assert_eq!(defaults.max_tokens, Some(2000));
+    assert!(defaults.stop.is_empty());
+    assert_eq!(defaults.seed, None);
+
+    let options = GenerateOptions::builder()
+        .prompt("Create a Move coin")
+        .language("move")
+        .mode(GenerationMode::Educational)
+        .temperature(0.2)
+        .max_tokens(512)
+        .stop("```")
+        .stop("END")
+        .seed(7)
+        .build();
+    assert_eq!(options.language.as_deref(), Some("move"));
+    assert!(matches!(options.mode, Some(GenerationMode::Educational)));
+    assert_eq!(options.temperature, Some(0.2));
+    assert_eq!(options.max_tokens, Some(512));
+    assert_eq!(options.stop, vec!["```", "END"]);
+    assert_eq!(options.seed, Some(7));
+}
+
+#[test]
+fn test_generate_options_try_build_validates() {
+    use craite::GenerateOptions;
+
+    let options = GenerateOptions::builder()
+        .prompt("Create a token")
+        .try_build()
+        .unwrap();
+    assert_eq!(options.max_tokens, Some(2000));
+
+    for temperature in [0.0, 2.0] {
+        assert!(GenerateOptions::builder()
+            .temperature(temperature)
+            .try_build()
+            .is_ok());
+    }
+    for temperature in [-0.1, 2.1, f32::NAN] {
+        let err = GenerateOptions::builder()
+            .temperature(temperature)
+            .try_build()
+            .unwrap_err();
+        assert!(err.to_string().contains("temperature"));
+    }
+
+    let err = GenerateOptions::builder()
+        .max_tokens(0)
+        .try_build()
+        .unwrap_err();
+    assert!(err.to_string().contains("max_tokens"));
+}
+
+#[test]
+fn test_debug_output_redacts_api_key() {
+    use craite::{CraiteClient, CraiteConfig};
+
+    let config = CraiteConfig {
+        api_key: "sk-super-secret".to_string(),
+        base_url: Some("http://localhost:8080/v1".to_string()),
+        ..Default::default()
+    };
+    let config_debug = format!("{:?}", config);
+    assert!(!config_debug.contains("sk-super-secret"));
+    assert!(config_debug.contains(r#"api_key: "***""#));
+    assert!(config_debug.contains("http://localhost:8080/v1"));
+
+    let client = CraiteClient::with_config(config).unwrap();
+    let client_debug = format!("{:?}", client);
+    assert!(!client_debug.contains("sk-super-secret"));
+    assert!(client_debug.contains(r#"api_key: "***""#));
+    assert!(client_debug.contains("http://localhost:8080/v1"));
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        "x-api-key",
+        reqwest::header::HeaderValue::from_static("gw-header-secret"),
+    );
+    let config = CraiteConfig {
+        api_key: "test-key".to_string(),
+        http_client: Some(
+            reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap(),
+        ),
+        ..Default::default()
+    };
+    let config_debug = format!("{:?}", config);
+    assert!(!config_debug.contains("gw-header-secret"));
+    assert!(config_debug.contains(r#"http_client: Some("<injected>")"#));
+    let client_debug = format!("{:?}", CraiteClient::with_config(config).unwrap());
+    assert!(!client_debug.contains("gw-header-secret"));
+}
+
+#[tokio::test]
+async fn test_prompt_template_render() {
+    use craite::PromptTemplate;
+
+    let template = PromptTemplate::new("Create a {kind} named {name}; keep {name} upgradeable { }");
+    assert_eq!(template.variables(), vec!["kind", "name"]);
+    assert_eq!(
+        template
+            .render(&[("kind", "ERC20"), ("name", "MyToken")])
+            .unwrap(),
+        "Create a ERC20 named MyToken; keep MyToken upgradeable { }"
+    );
+
+    let err = template.render(&[("kind", "ERC20")]).unwrap_err();
+    assert_eq!(err.to_string(), "Unresolved prompt variables: name");
+
+    let client = create_client("test-key");
+    let result = client
+        .generate_template(&template, &[("kind", "ERC721"), ("name", "Punks")])
+        .await
+        .unwrap();
+    assert!(result.code.contains("Create a ERC721 named Punks"));
+    assert!(client.generate_template(&template, &[]).await.is_err());
+}
+
+#[test]
+fn test_prompt_template_brace_escapes() {
+    use craite::PromptTemplate;
+
+    let template = PromptTemplate::new("Write {name}: function f() public {{revert}} }}{{");
+    assert_eq!(template.variables(), vec!["name"]);
+    assert_eq!(
+        template.render(&[("name", "Vault")]).unwrap(),
+        "Write Vault: function f() public {revert} }}{"
+    );
+
+    // Nested blocks end in `}}` without any escape; both braces survive
+    let template = PromptTemplate::new("Harden {name}: { if (a) { b(); }} {{ok}}");
+    assert_eq!(template.variables(), vec!["name"]);
+    assert_eq!(
+        template.render(&[("name", "Vault")]).unwrap(),
+        "Harden Vault: { if (a) { b(); }} {ok}"
+    );
+
+    // An escaped placeholder next to a real one
+    let template = PromptTemplate::new("{{{name}}}");
+    assert_eq!(template.render(&[("name", "x")]).unwrap(), "{x}");
+
+    let err = PromptTemplate::new("function f() public {revert}")
+        .render(&[])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Unresolved prompt variables: revert");
+}
+
+#[test]
+fn test_estimate_tokens() {
+    use craite::estimate_tokens;
+
+    assert_eq!(estimate_tokens("", "gpt-4o"), 0);
+
+    // 35 words of plain English, which real tokenizers split into a little
+    // over one token per word
+    let text = "Create an ERC-20 token with mint and burn functions, owned by the deployer, \
+                with a fixed supply of one million tokens and eighteen decimals. Make it \
+                pausable and add NatSpec comments to every public function.";
+    let estimate = estimate_tokens(text, "gpt-4o");
+    assert!(
+        (40..=70).contains(&estimate),
+        "estimate {} out of range",
+        estimate
+    );
+
+    assert!(estimate_tokens(text, "claude-3-5-sonnet-20241022") >= estimate);
+}
+
+#[test]
+fn test_save_to_file_uses_language_extension() {
+    use craite::client::GenerateResult;
+
+    let dir = std::env::temp_dir().join(format!("craite-save-{}", std::process::id()));
+    let result = GenerateResult {
+        code: "contract Token {}".to_string(),
+        language: Some("Solidity".to_string()),
+        ..Default::default()
+    };
+
+    let path = result.save_to_file(&dir).unwrap();
+    assert_eq!(path, dir.join("Token.sol"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "contract Token {}");
+
+    // A second save into the same directory keeps the first file
+    let second = GenerateResult {
+        code: "contract Token { uint256 v; }".to_string(),
+        ..result.clone()
+    };
+    assert_eq!(second.save_to_file(&dir).unwrap(), dir.join("Token-2.sol"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "contract Token {}");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("Token-2.sol")).unwrap(),
+        "contract Token { uint256 v; }"
+    );
+
+    let untyped = GenerateResult {
+        language: None,
+        ..result
+    };
+    assert_eq!(untyped.file_extension(), "txt");
+    assert_eq!(
+        untyped.save_to_file(&dir).unwrap(),
+        dir.join("generated.txt")
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_to_foundry_project_layout() {
+    use craite::client::GenerateResult;
+
+    let dir = std::env::temp_dir().join(format!("craite-foundry-{}", std::process::id()));
+    let result = GenerateResult {
+        code: "// SPDX-License-Identifier: MIT\n\
+               pragma solidity ^0.8.20;\n\
+               abstract contract Base {}\n\
+               contract DaoToken is Base {}\n"
+            .to_string(),
+        language: Some("solidity".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(result.contract_name(), Some("DaoToken"));
+
+    result.to_foundry_project(&dir).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("src/DaoToken.sol")).unwrap(),
+        result.code
+    );
+    assert!(std::fs::read_to_string(dir.join("foundry.toml"))
+        .unwrap()
+        .contains("[profile.default]"));
+    assert!(std::fs::read_to_string(dir.join("lib/README.md"))
+        .unwrap()
+        .contains("forge install OpenZeppelin/openzeppelin-contracts"));
+
+    // An existing project is left alone
+    let err = result.to_foundry_project(&dir).unwrap_err();
+    assert!(err.to_string().contains("already exists"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let no_contract = GenerateResult {
+        code: "library Math {}".to_string(),
+        ..result.clone()
+    };
+    assert!(no_contract.to_foundry_project(&dir).is_err());
+
+    let move_module = GenerateResult {
+        language: Some("move".to_string()),
+        ..result
+    };
+    let err = move_module.to_foundry_project(&dir).unwrap_err();
+    assert!(err.to_string().contains("Solidity"));
+    assert!(!dir.join("foundry.toml").exists());
+}
+
+#[test]
+fn test_contract_name_ignores_comments() {
+    use craite::client::GenerateResult;
+
+    let result = GenerateResult {
+        code: "contract A {}
+               /*
+               contract Legacy {}
+               */
+               // contract Old {}
+               contract B { string s = \"// not a comment\"; }
+               /* Ünïcode */ contract C {}
+               /**/
+"
+        .to_string(),
+        ..Default::default()
+    };
+    // Multi-byte characters in a blanked comment must not shift the name
+    assert_eq!(result.contract_name(), Some("C"));
+
+    let commented = GenerateResult {
+        code: "contract A {}\n/*\ncontract Legacy {}\n*/\n".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(commented.contract_name(), Some("A"));
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_max_concurrency_limits_in_flight_requests() {
+    use craite::client::GenerateResult;
+    use craite::{CraiteClient, CraiteConfig, MockProvider};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (active_in_mock, peak_in_mock) = (active.clone(), peak.clone());
+
+    let client = CraiteClient::with_config(CraiteConfig {
+        api_key: "test-key".to_string(),
+        max_concurrency: Some(2),
+        mock: Some(MockProvider::new_async(move |prompt| {
+            let (active, peak) = (active_in_mock.clone(), peak_in_mock.clone());
+            let code = prompt.to_string();
+            async move {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+                GenerateResult {
+                    code,
+                    ..Default::default()
+                }
+            }
+        })),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let tasks: Vec<_> = (0..8)
+        .map(|i| {
+            let client = client.clone();
+            tokio::spawn(async move { client.generate(&format!("task {}", i)).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    assert_eq!(active.load(Ordering::SeqCst), 0);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+    let err = CraiteClient::with_config(CraiteConfig {
+        max_concurrency: Some(0),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(err.to_string().contains("max_concurrency"));
+}